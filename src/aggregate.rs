@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use anyhow::Result;
+use async_std::fs;
+use async_std::prelude::*;
+use futures::stream::{FuturesUnordered, TryStreamExt};
+
+use crate::cli::AggregateOpt;
+use crate::merge::Merge;
+use crate::types::PlayerStatsheet;
+
+pub async fn run(opt: &AggregateOpt) -> Result<()> {
+    for season in opt.seasons.clone() {
+        // `dump` stores each season under a 0-indexed directory (it passes
+        // `season - 1` to `fetch_day`), but the aggregate's own `--seasons`
+        // flag, like dump's, is 1-indexed. Keep the on-disk lookup 0-indexed
+        // while keeping the written `season-<n>.json` name 1-indexed, so it
+        // matches what the user typed.
+        aggregate_season(&opt.out, season - 1, season).await?;
+    }
+    Ok(())
+}
+
+async fn aggregate_season(out: &Path, season_dir: usize, display_season: usize) -> Result<()> {
+    let players_dir = out.join("players");
+    let mut entries = fs::read_dir(&players_dir).await?;
+    let futures = FuturesUnordered::new();
+    while let Some(entry) = entries.next().await.transpose()? {
+        let player_dir = entry.path();
+        if !fs::metadata(&player_dir).await?.is_dir() {
+            continue;
+        }
+        futures.push(async_std::task::spawn(aggregate_player(
+            player_dir,
+            season_dir,
+            display_season,
+        )));
+    }
+    futures.try_collect::<()>().await
+}
+
+async fn aggregate_player(
+    player_dir: async_std::path::PathBuf,
+    season_dir: usize,
+    display_season: usize,
+) -> Result<()> {
+    let season_path = player_dir.join(season_dir.to_string());
+    if fs::metadata(&season_path).await.is_err() {
+        // No games were dumped for this player in this season.
+        return Ok(());
+    }
+
+    let player_id = player_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // `read_dir` order is filesystem-dependent, not numeric, but `Merge`
+    // takes non-numeric fields from whichever record is folded in last — so
+    // the per-day files must be visited in ascending day order.
+    let mut days = Vec::new();
+    let mut entries = fs::read_dir(&season_path).await?;
+    while let Some(entry) = entries.next().await.transpose()? {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let day = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<usize>().ok());
+        if let Some(day) = day {
+            days.push((day, path));
+        }
+    }
+    days.sort_unstable_by_key(|(day, _)| *day);
+
+    let mut total = PlayerStatsheet::identity(player_id);
+    for (_, path) in days {
+        let contents = fs::read_to_string(&path).await?;
+        let stats: PlayerStatsheet = serde_json::from_str(&contents)?;
+        total = total.merge(stats);
+    }
+
+    let aggregate_path = player_dir.join(format!("season-{}.json", display_season));
+    println!("writing {}", aggregate_path.display());
+    fs::write(&aggregate_path, serde_json::to_string(&total)?).await?;
+    println!("written {}", aggregate_path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    async fn write_day(dir: &std::path::Path, day: usize, hits: f64, note: &str) {
+        let mut extra = HashMap::new();
+        extra.insert("hits".to_string(), serde_json::json!(hits));
+        extra.insert("note".to_string(), serde_json::json!(note));
+        let stats = PlayerStatsheet {
+            id: format!("stat-{}", day),
+            player_id: "player-1".to_string(),
+            team_id: "team-1".to_string(),
+            extra,
+        };
+        let path = dir.join(format!("{}.json", day));
+        fs::write(&path, serde_json::to_string(&stats).unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[async_std::test]
+    async fn aggregate_season_reads_the_zero_indexed_directory_dump_writes() {
+        let out = tempfile::tempdir().unwrap();
+        // `dump --seasons 1` writes under directory "0".
+        let season_dir = out.path().join("players").join("player-1").join("0");
+        fs::create_dir_all(&season_dir).await.unwrap();
+        write_day(&season_dir, 0, 1.0, "first").await;
+        write_day(&season_dir, 1, 2.0, "second").await;
+
+        run(&AggregateOpt {
+            seasons: 1..2,
+            out: out.path().to_path_buf(),
+        })
+        .await
+        .unwrap();
+
+        let aggregate_path = out.path().join("players").join("player-1").join("season-1.json");
+        let contents = fs::read_to_string(&aggregate_path).await.unwrap();
+        let stats: PlayerStatsheet = serde_json::from_str(&contents).unwrap();
+        assert_eq!(stats.extra.get("hits").unwrap().as_f64(), Some(3.0));
+        assert_eq!(stats.extra.get("note").unwrap().as_str(), Some("second"));
+    }
+
+    #[async_std::test]
+    async fn aggregate_player_folds_days_in_numeric_not_lexicographic_order() {
+        let out = tempfile::tempdir().unwrap();
+        let season_dir = out.path().join("players").join("player-1").join("0");
+        fs::create_dir_all(&season_dir).await.unwrap();
+        // Lexicographically "10" sorts before "9", but day 10 is later.
+        write_day(&season_dir, 9, 1.0, "day-nine").await;
+        write_day(&season_dir, 10, 1.0, "day-ten").await;
+
+        aggregate_player(
+            out.path().join("players").join("player-1").into(),
+            0,
+            1,
+        )
+        .await
+        .unwrap();
+
+        let aggregate_path = out.path().join("players").join("player-1").join("season-1.json");
+        let contents = fs::read_to_string(&aggregate_path).await.unwrap();
+        let stats: PlayerStatsheet = serde_json::from_str(&contents).unwrap();
+        assert_eq!(stats.extra.get("note").unwrap().as_str(), Some("day-ten"));
+    }
+}
@@ -0,0 +1,191 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+use clap::{ArgEnum, Parser, Subcommand};
+
+/// Where dumped statsheets are written.
+#[derive(Debug, Clone, Copy, ArgEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum Store {
+    /// One JSON file per game and per player statsheet, under `--out`. Game
+    /// and team statsheets are not written.
+    Files,
+    /// Rows in a SQLite database at `--db`.
+    Sqlite,
+}
+
+#[derive(Debug, Parser)]
+#[clap(name = "statsheet-dumper")]
+pub struct Cli {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Dump game, team, and player statsheets for a range of seasons and days.
+    Dump(DumpOpt),
+    /// Combine dumped per-day player statsheets into season totals.
+    Aggregate(AggregateOpt),
+    /// Follow the live Blaseball event stream, dumping statsheets as games update.
+    Stream(StreamOpt),
+}
+
+#[derive(Debug, Parser)]
+pub struct DumpOpt {
+    /// Seasons to dump, 1-indexed (e.g. `1-11`, `5`). `a-b` and `a..=b`
+    /// include `b`; `a..b` excludes it.
+    #[clap(long, parse(try_from_str = parse_range))]
+    pub seasons: Range<usize>,
+
+    /// Days to dump within each season, 0-indexed (e.g. `0..50`). `a-b` and
+    /// `a..=b` include `b`; `a..b` excludes it.
+    #[clap(long, parse(try_from_str = parse_range), default_value = "0..99")]
+    pub days: Range<usize>,
+
+    /// Directory to write dumped statsheets into.
+    #[clap(long, default_value = "out")]
+    pub out: PathBuf,
+
+    /// Only dump games, skipping team and player statsheets.
+    #[clap(long)]
+    pub games_only: bool,
+
+    /// Skip dumping player statsheets.
+    #[clap(long)]
+    pub no_player_stats: bool,
+
+    /// Maximum number of HTTP requests to have in flight at once.
+    #[clap(long, default_value = "16")]
+    pub concurrency: usize,
+
+    /// Base delay for exponential backoff on retried requests, in milliseconds.
+    #[clap(long, default_value = "250")]
+    pub retry_base_delay_ms: u64,
+
+    /// Maximum number of retries for a single request before giving up.
+    #[clap(long, default_value = "5")]
+    pub max_retries: u32,
+
+    /// Storage backend to write dumped statsheets into.
+    #[clap(long, arg_enum, default_value = "files")]
+    pub store: Store,
+
+    /// Path to the SQLite database file, required when `--store sqlite`.
+    #[clap(long, required_if_eq("store", "sqlite"))]
+    pub db: Option<PathBuf>,
+
+    /// Skip days and statsheets already recorded in `manifest.json`.
+    #[clap(long)]
+    pub resume: bool,
+
+    /// Ignore `manifest.json` and refetch everything.
+    #[clap(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct AggregateOpt {
+    /// Seasons to aggregate, 1-indexed (e.g. `1-11`, `5`). `a-b` and `a..=b`
+    /// include `b`; `a..b` excludes it.
+    #[clap(long, parse(try_from_str = parse_range))]
+    pub seasons: Range<usize>,
+
+    /// Directory holding dumped per-day player statsheets; season aggregates
+    /// are written alongside them.
+    #[clap(long, default_value = "out")]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+pub struct StreamOpt {
+    /// Directory to write streamed statsheets into.
+    #[clap(long, default_value = "out")]
+    pub out: PathBuf,
+
+    /// Only dump games, skipping team and player statsheets.
+    #[clap(long)]
+    pub games_only: bool,
+
+    /// Skip dumping player statsheets.
+    #[clap(long)]
+    pub no_player_stats: bool,
+
+    /// Maximum number of HTTP requests to have in flight at once.
+    #[clap(long, default_value = "16")]
+    pub concurrency: usize,
+
+    /// Base delay for exponential backoff, in milliseconds: used both for
+    /// retried statsheet requests and for reconnecting the event stream.
+    #[clap(long, default_value = "250")]
+    pub retry_base_delay_ms: u64,
+
+    /// Maximum number of retries for a single statsheet request before giving up.
+    #[clap(long, default_value = "5")]
+    pub max_retries: u32,
+
+    /// Storage backend to write streamed statsheets into.
+    #[clap(long, arg_enum, default_value = "files")]
+    pub store: Store,
+
+    /// Path to the SQLite database file, required when `--store sqlite`.
+    #[clap(long, required_if_eq("store", "sqlite"))]
+    pub db: Option<PathBuf>,
+}
+
+/// Parses a range given as `a-b`, `a..b`, `a..=b`, or a single `a`.
+fn parse_range(s: &str) -> Result<Range<usize>, String> {
+    let err = || format!("invalid range `{}`, expected e.g. `1-11` or `0..50`", s);
+
+    if let Some((start, end)) = s.split_once("..=") {
+        let start = start.parse::<usize>().map_err(|_| err())?;
+        let end = end.parse::<usize>().map_err(|_| err())?;
+        return Ok(start..end + 1);
+    }
+
+    if let Some((start, end)) = s.split_once("..") {
+        let start = start.parse::<usize>().map_err(|_| err())?;
+        let end = end.parse::<usize>().map_err(|_| err())?;
+        return Ok(start..end);
+    }
+
+    if let Some((start, end)) = s.split_once('-') {
+        let start = start.parse::<usize>().map_err(|_| err())?;
+        let end = end.parse::<usize>().map_err(|_| err())?;
+        return Ok(start..end + 1);
+    }
+
+    let n = s.parse::<usize>().map_err(|_| err())?;
+    Ok(n..n + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_dash_range_as_inclusive() {
+        assert_eq!(parse_range("1-11").unwrap(), 1..12);
+    }
+
+    #[test]
+    fn parses_a_dotdot_range_as_exclusive() {
+        assert_eq!(parse_range("0..50").unwrap(), 0..50);
+    }
+
+    #[test]
+    fn parses_a_dotdoteq_range_as_inclusive() {
+        assert_eq!(parse_range("0..=50").unwrap(), 0..51);
+    }
+
+    #[test]
+    fn parses_a_single_value_as_a_one_element_range() {
+        assert_eq!(parse_range("5").unwrap(), 5..6);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse_range("eleven").is_err());
+        assert!(parse_range("1-").is_err());
+    }
+}
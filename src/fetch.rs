@@ -0,0 +1,218 @@
+use anyhow::Result;
+use async_std::task;
+use futures::stream::{FuturesUnordered, TryStreamExt};
+use std::sync::Arc;
+use surf::Client;
+
+use crate::cli::DumpOpt;
+use crate::manifest::{Artifacts, Manifest};
+use crate::request::RequestLimiter;
+use crate::storage::Storage;
+use crate::types::{GameStatsheet, GameUpdate, PlayerStatsheet, StatsheetsReq, TeamStatsheet};
+
+pub const PLAYER_STATS_URL: &str = "https://www.blaseball.com/database/playerSeasonStats";
+pub const GAMES_URL: &str = "https://www.blaseball.com/database/games";
+pub const GAME_STATSHEETS_URL: &str = "https://www.blaseball.com/database/gameStatsheets";
+pub const TEAM_STATSHEETS_URL: &str = "https://www.blaseball.com/database/teamStatsheets";
+
+#[allow(clippy::too_many_arguments)]
+async fn fetch_player_statsheets(
+    client: Client,
+    limiter: RequestLimiter,
+    storage: Storage,
+    manifest: Manifest,
+    resume: bool,
+    season: usize,
+    day: usize,
+    team_stats: Vec<TeamStatsheet>,
+) -> Result<()> {
+    let player_ids = team_stats
+        .iter()
+        .flat_map(|x| &x.player_stats)
+        .map(|x| &**x)
+        .collect::<Vec<&str>>()
+        .join(",");
+
+    println!("fetching day {} player statsheets", day);
+    let player_stats: Vec<PlayerStatsheet> = limiter
+        .get_json(
+            &client,
+            PLAYER_STATS_URL,
+            &StatsheetsReq { ids: player_ids },
+        )
+        .await?;
+    println!("received day {} player statsheets", day);
+
+    println!("writing day {} player statsheets", day);
+    let mut fresh = Vec::with_capacity(player_stats.len());
+    for stats in player_stats {
+        if resume && manifest.statsheet_done(&stats.id).await {
+            continue;
+        }
+        fresh.push(stats);
+    }
+
+    let futures: FuturesUnordered<_> = fresh
+        .into_iter()
+        .map(|x| {
+            let storage = storage.clone();
+            let manifest = manifest.clone();
+            task::spawn(async move {
+                let id = x.id.clone();
+                storage.write_player_statsheet(season, day, &x).await?;
+                manifest.mark_statsheet_done(&id, PLAYER_STATS_URL).await
+            })
+        })
+        .collect();
+    futures.try_collect::<()>().await?;
+
+    Ok(())
+}
+
+/// Fetches and writes the game/team/player statsheets for a batch of
+/// `games` that all belong to the same `(season, day)`. Shared by the
+/// historical day-by-day dump and the live event-stream follower.
+#[allow(clippy::too_many_arguments)]
+pub async fn process_games(
+    client: Client,
+    limiter: RequestLimiter,
+    storage: Storage,
+    manifest: Manifest,
+    resume: bool,
+    games_only: bool,
+    no_player_stats: bool,
+    season: usize,
+    day: usize,
+    games: Vec<GameUpdate>,
+) -> Result<()> {
+    let game_ids = games
+        .iter()
+        .map(|x| &*x.statsheet)
+        .collect::<Vec<&str>>()
+        .join(",");
+
+    let team_stats = if games_only {
+        Vec::new()
+    } else {
+        println!("fetching day {} game statsheets", day);
+        let game_stats: Vec<GameStatsheet> = limiter
+            .get_json(
+                &client,
+                GAME_STATSHEETS_URL,
+                &StatsheetsReq { ids: game_ids },
+            )
+            .await?;
+        println!("received day {} game statsheets", day);
+
+        let team_ids = game_stats
+            .iter()
+            .flat_map(|x| vec![&*x.away_team_stats, &*x.home_team_stats])
+            .collect::<Vec<&str>>()
+            .join(",");
+
+        for stats in &game_stats {
+            storage.write_game_statsheet(season, day, stats).await?;
+        }
+
+        println!("fetching day {} team statsheets", day);
+        let team_stats: Vec<TeamStatsheet> = limiter
+            .get_json(
+                &client,
+                TEAM_STATSHEETS_URL,
+                &StatsheetsReq { ids: team_ids },
+            )
+            .await?;
+        println!("received day {} team statsheets", day);
+
+        for stats in &team_stats {
+            storage.write_team_statsheet(season, day, stats).await?;
+        }
+
+        team_stats
+    };
+
+    println!("writing day {} games", day);
+    let futures: FuturesUnordered<task::JoinHandle<Result<()>>> = FuturesUnordered::new();
+    for game in games {
+        if resume && manifest.statsheet_done(&game.id).await {
+            continue;
+        }
+        let storage = storage.clone();
+        let manifest = manifest.clone();
+        futures.push(task::spawn(async move {
+            let id = game.id.clone();
+            storage.write_game(season, day, &game).await?;
+            manifest.mark_statsheet_done(&id, GAMES_URL).await
+        }));
+    }
+
+    if !games_only && !no_player_stats {
+        for chunk in team_stats.chunks(5) {
+            let client = client.clone();
+            let limiter = limiter.clone();
+            let storage = storage.clone();
+            let manifest = manifest.clone();
+            futures.push(task::spawn(fetch_player_statsheets(
+                client,
+                limiter,
+                storage,
+                manifest,
+                resume,
+                season,
+                day,
+                chunk.to_vec(),
+            )));
+        }
+    }
+
+    futures.try_collect::<()>().await?;
+    println!("finished day {}", day);
+
+    Ok(())
+}
+
+pub async fn fetch_day(
+    client: Client,
+    limiter: RequestLimiter,
+    storage: Storage,
+    manifest: Manifest,
+    opt: Arc<DumpOpt>,
+    season: usize,
+    day: usize,
+) -> Result<()> {
+    #[derive(serde::Serialize)]
+    struct Games {
+        season: usize,
+        day: usize,
+    }
+
+    let artifacts = Artifacts::from_opt(opt.games_only, opt.no_player_stats);
+    if opt.resume && manifest.day_done(season, day, artifacts).await {
+        println!("skipping day {} (already in manifest)", day);
+        return Ok(());
+    }
+
+    println!("fetching day {}", day);
+    let games: Vec<GameUpdate> = limiter
+        .get_json(&client, GAMES_URL, &Games { season, day })
+        .await?;
+    println!("received day {}", day);
+
+    process_games(
+        client,
+        limiter,
+        storage,
+        manifest.clone(),
+        opt.resume,
+        opt.games_only,
+        opt.no_player_stats,
+        season,
+        day,
+        games,
+    )
+    .await?;
+
+    manifest.mark_day_done(season, day, GAMES_URL, artifacts).await?;
+
+    Ok(())
+}
@@ -1,203 +1,57 @@
-use anyhow::{anyhow, Error, Result};
-use async_std::fs;
-use async_std::task;
+mod aggregate;
+mod cli;
+mod fetch;
+mod manifest;
+mod merge;
+mod request;
+mod storage;
+mod stream;
+mod types;
+
+use anyhow::Result;
+use clap::Parser;
 use futures::stream::{FuturesUnordered, TryStreamExt};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::env;
-use std::path::PathBuf;
+use std::sync::Arc;
 use surf::Client;
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GameUpdate {
-    pub id: String,
-    pub statsheet: String,
-    pub away_team: String,
-    pub home_team: String,
+use cli::{Cli, Command, DumpOpt};
+use fetch::fetch_day;
+use manifest::Manifest;
+use request::RequestLimiter;
+use storage::Storage;
 
-    #[serde(flatten)]
-    pub extra: HashMap<String, serde_json::Value>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GameStatsheet {
-    pub away_team_stats: String,
-    pub home_team_stats: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct TeamStatsheet {
-    pub player_stats: Vec<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct PlayerStatsheet {
-    pub id: String,
-    pub player_id: String,
-    pub team_id: String,
-
-    #[serde(flatten)]
-    pub extra: HashMap<String, serde_json::Value>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct StatsheetsReq {
-    pub ids: String,
-}
-
-fn surf_error(error: surf::Error) -> Error {
-    anyhow!("{}", error)
-}
-
-async fn write_player_statsheet(day: usize, stats: PlayerStatsheet) -> Result<()> {
-    let mut path = PathBuf::from("out");
-    path.push("players");
-    path.push(&stats.player_id);
-    fs::create_dir_all(&path).await?;
-    path.push(&day.to_string());
-    path.set_extension("json");
-    println!("writing {}", path.display());
-    fs::write(&path, &serde_json::to_string(&stats)?).await?;
-    println!("written {}", path.display());
-    Ok(())
-}
-
-async fn write_game(day: usize, game: GameUpdate) -> Result<()> {
-    let mut path = PathBuf::from("out");
-    path.push("games");
-    path.push(&day.to_string());
-    fs::create_dir_all(&path).await?;
-    path.push(&game.home_team);
-    path.set_extension("json");
-    println!("writing day {}", day);
-    fs::write(&path, &serde_json::to_string(&game)?).await?;
-    println!("written day {}", day);
-    Ok(())
-}
-
-async fn fetch_player_statsheets(
-    client: Client,
-    day: usize,
-    team_stats: Vec<TeamStatsheet>,
-) -> Result<()> {
-    let player_ids = team_stats
-        .iter()
-        .flat_map(|x| &x.player_stats)
-        .map(|x| &**x)
-        .collect::<Vec<&str>>()
-        .join(",");
-
-    println!("fetching day {} player statsheets", day);
-    let player_stats: Vec<PlayerStatsheet> = client
-        .get("https://www.blaseball.com/database/playerSeasonStats")
-        .query(&StatsheetsReq { ids: player_ids })
-        .map_err(surf_error)?
-        .await
-        .map_err(surf_error)?
-        .body_json()
-        .await
-        .map_err(surf_error)?;
-    println!("received day {} player statsheets", day);
-
-    println!("writing day {} player statsheets", day);
-    let futures: FuturesUnordered<_> = player_stats
-        .into_iter()
-        .map(|x| task::spawn(write_player_statsheet(day, x)))
-        .collect();
-    futures.try_collect::<()>().await?;
-
-    Ok(())
-}
-
-async fn fetch_day(client: Client, season: usize, day: usize) -> Result<()> {
-    #[derive(Serialize)]
-    struct Games {
-        season: usize,
-        day: usize,
+#[async_std::main]
+async fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Dump(opt) => dump(opt).await,
+        Command::Aggregate(opt) => aggregate::run(&opt).await,
+        Command::Stream(opt) => stream::run(&opt).await,
     }
-
-    println!("fetching day {}", day);
-    let games: Vec<GameUpdate> = client
-        .get("https://www.blaseball.com/database/games")
-        .query(&Games { season, day })
-        .map_err(surf_error)?
-        .await
-        .map_err(surf_error)?
-        .body_json()
-        .await
-        .map_err(surf_error)?;
-    println!("received day {}", day);
-
-    let game_ids = games
-        .iter()
-        .map(|x| &*x.statsheet)
-        .collect::<Vec<&str>>()
-        .join(",");
-
-    println!("fetching day {} game statsheets", day);
-    let game_stats: Vec<GameStatsheet> = client
-        .get("https://www.blaseball.com/database/gameStatsheets")
-        .query(&StatsheetsReq { ids: game_ids })
-        .map_err(surf_error)?
-        .await
-        .map_err(surf_error)?
-        .body_json()
-        .await
-        .map_err(surf_error)?;
-    println!("received day {} team statsheets", day);
-
-    let team_ids = game_stats
-        .iter()
-        .flat_map(|x| vec![&*x.away_team_stats, &*x.home_team_stats])
-        .collect::<Vec<&str>>()
-        .join(",");
-
-    println!("fetching day {} team statsheets", day);
-    let team_stats: Vec<TeamStatsheet> = client
-        .get("https://www.blaseball.com/database/teamStatsheets")
-        .query(&StatsheetsReq { ids: team_ids })
-        .map_err(surf_error)?
-        .await
-        .map_err(surf_error)?
-        .body_json()
-        .await
-        .map_err(surf_error)?;
-    println!("received day {} team statsheets", day);
-
-    println!("fetching day {} player statsheets", day);
-    println!("writing day {} games", day);
-    let futures: FuturesUnordered<_> = team_stats
-        .chunks(5)
-        .map(|x| {
-            let client = client.clone();
-            task::spawn(fetch_player_statsheets(client, day, x.to_vec()))
-        })
-        .chain(games.into_iter().map(|x| task::spawn(write_game(day, x))))
-        .collect();
-    futures.try_collect::<()>().await?;
-    println!("finished day {}", day);
-
-    Ok(())
 }
 
-#[async_std::main]
-async fn main() -> Result<()> {
-    let season = env::args()
-        .nth(1)
-        .ok_or_else(|| anyhow!("Missing season!"))?
-        .parse::<usize>()?
-        - 1;
+async fn dump(opt: DumpOpt) -> Result<()> {
+    let opt = Arc::new(opt);
     let client = Client::new();
-    let futures: FuturesUnordered<_> = (0..99)
-        .map(|day| {
+    let limiter = RequestLimiter::new(opt.concurrency, opt.retry_base_delay_ms, opt.max_retries);
+    let storage = Storage::open(opt.store, opt.out.clone(), opt.db.clone()).await?;
+    let manifest = Manifest::load(&opt.out, opt.force).await?;
+
+    let futures: FuturesUnordered<_> = opt
+        .seasons
+        .clone()
+        .flat_map(|season| opt.days.clone().map(move |day| (season, day)))
+        .map(|(season, day)| {
             let client = client.clone();
-            task::spawn(fetch_day(client, season, day))
+            let limiter = limiter.clone();
+            let storage = storage.clone();
+            let manifest = manifest.clone();
+            let opt = opt.clone();
+            async_std::task::spawn(async move {
+                fetch_day(client, limiter, storage, manifest, opt, season - 1, day).await
+            })
         })
         .collect();
     futures.try_collect::<()>().await?;
+
     Ok(())
 }
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_std::fs;
+use async_std::sync::Mutex;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub written_at: String,
+    pub url: String,
+}
+
+/// Which statsheets a dump run fetched for a day, from least to most
+/// complete. Derived from `--games-only`/`--no-player-stats`, and ordered so
+/// that `a >= b` means "a run with artifact set `a` fetched everything a run
+/// asking for `b` would have fetched".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Artifacts {
+    GamesOnly,
+    NoPlayerStats,
+    Full,
+}
+
+impl Artifacts {
+    pub fn from_opt(games_only: bool, no_player_stats: bool) -> Self {
+        if games_only {
+            Artifacts::GamesOnly
+        } else if no_player_stats {
+            Artifacts::NoPlayerStats
+        } else {
+            Artifacts::Full
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayEntry {
+    pub written_at: String,
+    pub url: String,
+    pub artifacts: Artifacts,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    #[serde(default)]
+    days: HashMap<String, DayEntry>,
+    #[serde(default)]
+    statsheets: HashMap<String, Entry>,
+}
+
+/// Tracks which `(season, day)` pairs and statsheet ids have already been
+/// written, so a later `--resume` run can skip them. Persisted as
+/// `manifest.json` in the output directory and updated atomically (write to
+/// a temp file, then rename) after each successful write.
+///
+/// `state` is guarded by an async mutex held across the whole
+/// read-modify-write-and-rename sequence in `save`, not just the in-memory
+/// update — dozens of statsheet writes can finish concurrently per day, and
+/// two overlapping saves racing on the same temp file would corrupt it or
+/// make the second `rename` fail outright.
+#[derive(Clone)]
+pub struct Manifest {
+    path: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+impl Manifest {
+    pub async fn load(out: &Path, force: bool) -> Result<Self> {
+        let path = out.join("manifest.json");
+        let state = if force {
+            State::default()
+        } else {
+            match fs::read_to_string(&path).await {
+                Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+                Err(_) => State::default(),
+            }
+        };
+        Ok(Manifest {
+            path,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Whether `(season, day)` was already fetched with at least as complete
+    /// an artifact set as `artifacts` — so e.g. a prior `--games-only` run
+    /// doesn't cause a later plain `--resume` run to skip backfilling team
+    /// and player stats for that day.
+    pub async fn day_done(&self, season: usize, day: usize, artifacts: Artifacts) -> bool {
+        self.state
+            .lock()
+            .await
+            .days
+            .get(&day_key(season, day))
+            .map(|entry| entry.artifacts >= artifacts)
+            .unwrap_or(false)
+    }
+
+    pub async fn mark_day_done(
+        &self,
+        season: usize,
+        day: usize,
+        url: &str,
+        artifacts: Artifacts,
+    ) -> Result<()> {
+        let mut state = self.state.lock().await;
+        state.days.insert(
+            day_key(season, day),
+            DayEntry {
+                written_at: Utc::now().to_rfc3339(),
+                url: url.to_string(),
+                artifacts,
+            },
+        );
+        self.save(&state).await
+    }
+
+    pub async fn statsheet_done(&self, id: &str) -> bool {
+        self.state.lock().await.statsheets.contains_key(id)
+    }
+
+    pub async fn mark_statsheet_done(&self, id: &str, url: &str) -> Result<()> {
+        let mut state = self.state.lock().await;
+        state.statsheets.insert(
+            id.to_string(),
+            Entry {
+                written_at: Utc::now().to_rfc3339(),
+                url: url.to_string(),
+            },
+        );
+        self.save(&state).await
+    }
+
+    /// Writes `state` to a temp file and renames it into place. Takes `state`
+    /// by reference from a lock the caller is still holding, so concurrent
+    /// callers serialize on the same mutex instead of racing on the temp file.
+    async fn save(&self, state: &State) -> Result<()> {
+        let contents = serde_json::to_string_pretty(state)?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, contents).await?;
+        fs::rename(&tmp, &self.path).await?;
+        Ok(())
+    }
+}
+
+fn day_key(season: usize, day: usize) -> String {
+    format!("{}/{}", season, day)
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::types::PlayerStatsheet;
+
+/// Combines two records covering the same entity into one running total.
+///
+/// `self` is treated as the earlier record and `other` as the later one:
+/// numeric `extra` fields are summed (a field missing from one side counts
+/// as 0), while fields numeric on neither side are taken from `other`. A
+/// numeric value in `self` is kept when `other` holds a non-numeric one.
+pub trait Merge {
+    fn merge(self, other: Self) -> Self;
+}
+
+impl Merge for PlayerStatsheet {
+    fn merge(self, other: Self) -> Self {
+        PlayerStatsheet {
+            id: other.id,
+            player_id: other.player_id,
+            team_id: other.team_id,
+            extra: merge_extra(self.extra, other.extra),
+        }
+    }
+}
+
+impl PlayerStatsheet {
+    /// The identity element for [`Merge`]: folding it with any statsheet
+    /// yields that statsheet, except that numeric `extra` values (including
+    /// numeric strings) come out as floating-point JSON numbers.
+    pub fn identity(player_id: String) -> Self {
+        PlayerStatsheet {
+            id: String::new(),
+            player_id,
+            team_id: String::new(),
+            extra: HashMap::new(),
+        }
+    }
+}
+
+/// Coerces a JSON number or numeric string to `f64`; anything else (including
+/// an unparseable string) yields `None`.
+fn coerce_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn merge_extra(
+    mut earlier: HashMap<String, Value>,
+    later: HashMap<String, Value>,
+) -> HashMap<String, Value> {
+    for (key, later_value) in later {
+        let earlier_value = earlier.remove(&key);
+        let earlier_num = earlier_value.as_ref().and_then(coerce_f64);
+        let merged = match (earlier_num, coerce_f64(&later_value)) {
+            (Some(earlier_num), Some(later_num)) => json!(earlier_num + later_num),
+            (None, Some(later_num)) => json!(later_num),
+            // The later day's value is non-numeric or unparseable (e.g. a
+            // glitchy "DNP"): if a numeric running total exists, keep it
+            // rather than letting garbage clobber it. Only a true non-numeric
+            // field, where neither side is numeric, takes the later value.
+            (Some(_), None) => earlier_value.unwrap(),
+            (None, None) => later_value,
+        };
+        earlier.insert(key, merged);
+    }
+    earlier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coerce_f64_reads_numbers_and_numeric_strings() {
+        assert_eq!(coerce_f64(&json!(3)), Some(3.0));
+        assert_eq!(coerce_f64(&json!("3")), Some(3.0));
+        assert_eq!(coerce_f64(&json!("not a number")), None);
+        assert_eq!(coerce_f64(&json!(true)), None);
+        assert_eq!(coerce_f64(&json!(null)), None);
+    }
+
+    #[test]
+    fn merge_extra_sums_numeric_fields_present_on_both_sides() {
+        let earlier = HashMap::from([("hits".to_string(), json!(2))]);
+        let later = HashMap::from([("hits".to_string(), json!(3))]);
+        let merged = merge_extra(earlier, later);
+        assert_eq!(merged["hits"].as_f64(), Some(5.0));
+    }
+
+    #[test]
+    fn merge_extra_sums_across_number_and_string_representations() {
+        let earlier = HashMap::from([("hits".to_string(), json!("2"))]);
+        let later = HashMap::from([("hits".to_string(), json!(3))]);
+        let merged = merge_extra(earlier, later);
+        assert_eq!(merged["hits"].as_f64(), Some(5.0));
+    }
+
+    #[test]
+    fn merge_extra_treats_a_key_missing_on_one_side_as_zero() {
+        let earlier = HashMap::from([("hits".to_string(), json!(2))]);
+        let later = HashMap::new();
+        let merged = merge_extra(earlier, later);
+        assert_eq!(merged["hits"].as_f64(), Some(2.0));
+
+        let earlier = HashMap::new();
+        let later = HashMap::from([("hits".to_string(), json!(3))]);
+        let merged = merge_extra(earlier, later);
+        assert_eq!(merged["hits"].as_f64(), Some(3.0));
+    }
+
+    #[test]
+    fn merge_extra_takes_the_later_value_for_non_numeric_fields() {
+        let earlier = HashMap::from([("name".to_string(), json!("Jacob"))]);
+        let later = HashMap::from([("name".to_string(), json!("Jaylen"))]);
+        let merged = merge_extra(earlier, later);
+        assert_eq!(merged["name"], json!("Jaylen"));
+    }
+
+    #[test]
+    fn merge_extra_prefers_the_later_numeric_value_when_the_earlier_side_changed_type() {
+        // A field that used to be a free-text field and became numeric
+        // shouldn't have the old string coerced into the sum.
+        let earlier = HashMap::from([("status".to_string(), json!("unknown"))]);
+        let later = HashMap::from([("status".to_string(), json!(1))]);
+        let merged = merge_extra(earlier, later);
+        assert_eq!(merged["status"].as_f64(), Some(1.0));
+    }
+
+    #[test]
+    fn merge_extra_keeps_the_running_total_when_the_later_value_is_unparseable() {
+        // A glitchy day reporting "DNP" for a numeric field shouldn't erase
+        // the season total accumulated so far.
+        let earlier = HashMap::from([("hits".to_string(), json!(5))]);
+        let later = HashMap::from([("hits".to_string(), json!("DNP"))]);
+        let merged = merge_extra(earlier, later);
+        assert_eq!(merged["hits"].as_f64(), Some(5.0));
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Error, Result};
+use async_lock::Semaphore;
+use async_std::task;
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use surf::{Client, StatusCode};
+
+/// Caps in-flight HTTP requests to a configurable concurrency and retries
+/// transient failures (connection errors, timeouts, 429s, 5xxs) with
+/// exponential backoff and jitter, honoring `Retry-After` when it is given
+/// in seconds (an HTTP-date value falls back to the backoff delay).
+/// Anything else — a permanent 4xx, or a malformed response body — fails
+/// immediately instead of burning the retry budget on something that will
+/// never succeed.
+#[derive(Clone)]
+pub struct RequestLimiter {
+    semaphore: Arc<Semaphore>,
+    base_delay: Duration,
+    max_retries: u32,
+}
+
+/// Whether a failed attempt is worth retrying.
+enum Failure {
+    Transient { error: Error, retry_after: Option<Duration> },
+    Fatal(Error),
+}
+
+impl RequestLimiter {
+    pub fn new(concurrency: usize, retry_base_delay_ms: u64, max_retries: u32) -> Self {
+        RequestLimiter {
+            semaphore: Arc::new(Semaphore::new(concurrency)),
+            base_delay: Duration::from_millis(retry_base_delay_ms),
+            max_retries,
+        }
+    }
+
+    /// GETs `url` with `query` attached, retrying transient failures, and
+    /// deserializes the JSON response body as `T`.
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        client: &Client,
+        url: &str,
+        query: &impl Serialize,
+    ) -> Result<T> {
+        let _permit = self.semaphore.acquire().await;
+
+        let mut attempt = 0;
+        loop {
+            match self.try_get_json(client, url, query).await {
+                Ok(value) => return Ok(value),
+                Err(Failure::Fatal(err)) => return Err(err),
+                Err(Failure::Transient { error, retry_after }) if attempt < self.max_retries => {
+                    attempt += 1;
+                    let delay = retry_after.unwrap_or_else(|| self.backoff_delay(attempt));
+                    println!(
+                        "request to {} failed ({}), retrying (attempt {}/{}) in {:?}",
+                        url, error, attempt, self.max_retries, delay
+                    );
+                    task::sleep(delay).await;
+                }
+                Err(Failure::Transient { error, .. }) => return Err(error),
+            }
+        }
+    }
+
+    async fn try_get_json<T: DeserializeOwned>(
+        &self,
+        client: &Client,
+        url: &str,
+        query: &impl Serialize,
+    ) -> std::result::Result<T, Failure> {
+        // Building the request (serializing `query`) can only fail because
+        // of a bug in the caller, not a flaky network — never retry it.
+        let request = client
+            .get(url)
+            .query(query)
+            .map_err(|e| Failure::Fatal(surf_error(e)))?;
+
+        // Issuing it, on the other hand, is exactly the connection-error /
+        // timeout case we do want to retry.
+        let mut response = request.await.map_err(|e| Failure::Transient {
+            error: surf_error(e),
+            retry_after: None,
+        })?;
+
+        let status = response.status();
+        if status.is_server_error() || status == StatusCode::TooManyRequests {
+            return Err(Failure::Transient {
+                error: anyhow!("{} returned {}", url, status),
+                retry_after: retry_after_delay(&response),
+            });
+        }
+        if status.is_client_error() {
+            return Err(Failure::Fatal(anyhow!(
+                "{} returned {} (not retryable)",
+                url,
+                status
+            )));
+        }
+
+        response
+            .body_json()
+            .await
+            .map_err(|e| Failure::Fatal(surf_error(e)))
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exponential = self.base_delay.mul_f64(2f64.powi(attempt as i32 - 1));
+        let jitter = self.base_delay.mul_f64(rand::thread_rng().gen::<f64>());
+        exponential + jitter
+    }
+}
+
+fn retry_after_delay(response: &surf::Response) -> Option<Duration> {
+    let seconds = response.header("Retry-After")?.last().as_str().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+fn surf_error(error: surf::Error) -> Error {
+    anyhow!("{}", error)
+}
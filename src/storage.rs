@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use async_std::fs;
+use async_std::task;
+use rusqlite::{params, Connection};
+
+use crate::cli::Store as StoreKind;
+use crate::types::{GameStatsheet, GameUpdate, PlayerStatsheet, TeamStatsheet};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS games (
+    id TEXT NOT NULL,
+    season INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    extra TEXT NOT NULL,
+    PRIMARY KEY (id, season, day)
+);
+CREATE TABLE IF NOT EXISTS game_statsheets (
+    id TEXT NOT NULL,
+    season INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    extra TEXT NOT NULL,
+    PRIMARY KEY (id, season, day)
+);
+CREATE TABLE IF NOT EXISTS team_statsheets (
+    id TEXT NOT NULL,
+    season INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    extra TEXT NOT NULL,
+    PRIMARY KEY (id, season, day)
+);
+CREATE TABLE IF NOT EXISTS player_statsheets (
+    id TEXT NOT NULL,
+    player_id TEXT NOT NULL,
+    season INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    extra TEXT NOT NULL,
+    PRIMARY KEY (id, season, day)
+);
+";
+
+/// Where dumped statsheets end up: either per-file JSON, or upserted rows in
+/// a SQLite database.
+#[derive(Clone)]
+pub enum Storage {
+    Files { out: PathBuf },
+    Sqlite { conn: Arc<Mutex<Connection>> },
+}
+
+impl Storage {
+    pub async fn open(store: StoreKind, out: PathBuf, db: Option<PathBuf>) -> Result<Self> {
+        match store {
+            StoreKind::Files => Ok(Storage::Files { out }),
+            StoreKind::Sqlite => {
+                let db = db.ok_or_else(|| anyhow!("--db is required when --store sqlite"))?;
+                let conn = task::spawn_blocking(move || -> Result<Connection> {
+                    let conn = Connection::open(&db)?;
+                    conn.execute_batch(SCHEMA)?;
+                    Ok(conn)
+                })
+                .await?;
+                Ok(Storage::Sqlite {
+                    conn: Arc::new(Mutex::new(conn)),
+                })
+            }
+        }
+    }
+
+    pub async fn write_game(&self, season: usize, day: usize, game: &GameUpdate) -> Result<()> {
+        match self {
+            Storage::Files { out } => write_game_file(out, season, day, game).await,
+            Storage::Sqlite { conn } => {
+                upsert(conn, "games", &game.id, season, day, game).await
+            }
+        }
+    }
+
+    pub async fn write_game_statsheet(
+        &self,
+        season: usize,
+        day: usize,
+        stats: &GameStatsheet,
+    ) -> Result<()> {
+        match self {
+            // The files backend only ever dumped games and player statsheets.
+            Storage::Files { .. } => Ok(()),
+            Storage::Sqlite { conn } => {
+                upsert(conn, "game_statsheets", &stats.id, season, day, stats).await
+            }
+        }
+    }
+
+    pub async fn write_team_statsheet(
+        &self,
+        season: usize,
+        day: usize,
+        stats: &TeamStatsheet,
+    ) -> Result<()> {
+        match self {
+            Storage::Files { .. } => Ok(()),
+            Storage::Sqlite { conn } => {
+                upsert(conn, "team_statsheets", &stats.id, season, day, stats).await
+            }
+        }
+    }
+
+    pub async fn write_player_statsheet(
+        &self,
+        season: usize,
+        day: usize,
+        stats: &PlayerStatsheet,
+    ) -> Result<()> {
+        match self {
+            Storage::Files { out } => write_player_statsheet_file(out, season, day, stats).await,
+            Storage::Sqlite { conn } => {
+                let conn = conn.clone();
+                let id = stats.id.clone();
+                let player_id = stats.player_id.clone();
+                let extra = serde_json::to_string(stats)?;
+                task::spawn_blocking(move || -> Result<()> {
+                    conn.lock().unwrap().execute(
+                        "INSERT INTO player_statsheets (id, player_id, season, day, extra)
+                         VALUES (?1, ?2, ?3, ?4, ?5)
+                         ON CONFLICT (id, season, day) DO UPDATE SET extra = excluded.extra",
+                        params![id, player_id, season as i64, day as i64, extra],
+                    )?;
+                    Ok(())
+                })
+                .await
+            }
+        }
+    }
+}
+
+/// Inserts or replaces a row keyed by `(id, season, day)`, storing the whole
+/// record as a JSON column.
+async fn upsert(
+    conn: &Arc<Mutex<Connection>>,
+    table: &'static str,
+    id: &str,
+    season: usize,
+    day: usize,
+    record: &impl serde::Serialize,
+) -> Result<()> {
+    let conn = conn.clone();
+    let id = id.to_string();
+    let extra = serde_json::to_string(record)?;
+    task::spawn_blocking(move || -> Result<()> {
+        conn.lock().unwrap().execute(
+            &format!(
+                "INSERT INTO {table} (id, season, day, extra) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (id, season, day) DO UPDATE SET extra = excluded.extra",
+                table = table
+            ),
+            params![id, season as i64, day as i64, extra],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+async fn write_player_statsheet_file(
+    out: &std::path::Path,
+    season: usize,
+    day: usize,
+    stats: &PlayerStatsheet,
+) -> Result<()> {
+    let mut path = out.to_path_buf();
+    path.push("players");
+    path.push(&stats.player_id);
+    path.push(season.to_string());
+    fs::create_dir_all(&path).await?;
+    path.push(day.to_string());
+    path.set_extension("json");
+    println!("writing {}", path.display());
+    fs::write(&path, &serde_json::to_string(stats)?).await?;
+    println!("written {}", path.display());
+    Ok(())
+}
+
+async fn write_game_file(
+    out: &std::path::Path,
+    season: usize,
+    day: usize,
+    game: &GameUpdate,
+) -> Result<()> {
+    let mut path = out.to_path_buf();
+    path.push("games");
+    path.push(season.to_string());
+    path.push(day.to_string());
+    fs::create_dir_all(&path).await?;
+    path.push(&game.home_team);
+    path.set_extension("json");
+    println!("writing day {}", day);
+    fs::write(&path, &serde_json::to_string(game)?).await?;
+    println!("written day {}", day);
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use async_std::task;
+use rand::Rng;
+use serde_json::Value;
+use surf::Client;
+
+use crate::cli::StreamOpt;
+use crate::fetch::process_games;
+use crate::manifest::Manifest;
+use crate::request::RequestLimiter;
+use crate::storage::Storage;
+use crate::types::GameUpdate;
+
+const STREAM_URL: &str = "https://www.blaseball.com/events/streamData";
+
+/// Follows the live Blaseball event stream and dumps statsheets for games as
+/// they update, reconnecting with exponential backoff on disconnect.
+pub async fn run(opt: &StreamOpt) -> Result<()> {
+    let client = Client::new();
+    let limiter = RequestLimiter::new(opt.concurrency, opt.retry_base_delay_ms, opt.max_retries);
+    let storage = Storage::open(opt.store, opt.out.clone(), opt.db.clone()).await?;
+    let manifest = Manifest::load(&opt.out, false).await?;
+    let base_delay = Duration::from_millis(opt.retry_base_delay_ms);
+
+    // Tracks the last-seen content hash per game id so unchanged ticks
+    // (the common case; Blaseball sends one every few seconds) are skipped
+    // instead of re-fetching and rewriting statsheets that haven't changed.
+    let mut seen: HashMap<String, u64> = HashMap::new();
+    let mut attempt: u32 = 0;
+
+    loop {
+        match follow(&client, &limiter, &storage, &manifest, opt, &mut seen).await {
+            Ok(()) => attempt = 0,
+            Err(err) => {
+                attempt += 1;
+                let delay = backoff_delay(base_delay, attempt);
+                eprintln!(
+                    "stream disconnected ({}), reconnecting in {:?}",
+                    err, delay
+                );
+                task::sleep(delay).await;
+            }
+        }
+    }
+}
+
+async fn follow(
+    client: &Client,
+    limiter: &RequestLimiter,
+    storage: &Storage,
+    manifest: &Manifest,
+    opt: &StreamOpt,
+    seen: &mut HashMap<String, u64>,
+) -> Result<()> {
+    println!("connecting to {}", STREAM_URL);
+    let response = client.get(STREAM_URL).await.map_err(|e| anyhow!("{}", e))?;
+    let mut lines = BufReader::new(response).lines();
+
+    while let Some(line) = lines.next().await.transpose()? {
+        let data = match line.strip_prefix("data:") {
+            Some(data) if !data.trim().is_empty() => data.trim(),
+            _ => continue,
+        };
+
+        let event: Value = serde_json::from_str(data)?;
+        for (season, day, games) in group_by_day(extract_games(&event)) {
+            let fresh: Vec<GameUpdate> = games
+                .into_iter()
+                .filter(|game| {
+                    let hash = hash_game(game);
+                    seen.insert(game.id.clone(), hash) != Some(hash)
+                })
+                .collect();
+            if fresh.is_empty() {
+                continue;
+            }
+
+            process_games(
+                client.clone(),
+                limiter.clone(),
+                storage.clone(),
+                manifest.clone(),
+                false,
+                opt.games_only,
+                opt.no_player_stats,
+                season,
+                day,
+                fresh,
+            )
+            .await?;
+        }
+    }
+
+    Err(anyhow!("event stream closed"))
+}
+
+/// Pulls the live schedule out of a `streamData` event payload.
+fn extract_games(event: &Value) -> Vec<GameUpdate> {
+    event
+        .pointer("/value/games/schedule")
+        .and_then(Value::as_array)
+        .map(|schedule| {
+            schedule
+                .iter()
+                .filter_map(|game| serde_json::from_value(game.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn game_season_day(game: &GameUpdate) -> Option<(usize, usize)> {
+    let season = game.extra.get("season")?.as_u64()? as usize;
+    let day = game.extra.get("day")?.as_u64()? as usize;
+    Some((season, day))
+}
+
+fn group_by_day(games: Vec<GameUpdate>) -> Vec<(usize, usize, Vec<GameUpdate>)> {
+    let mut groups: Vec<(usize, usize, Vec<GameUpdate>)> = Vec::new();
+    for game in games {
+        let key = match game_season_day(&game) {
+            Some(key) => key,
+            // Games without a season/day (e.g. an all-star game) aren't dumped.
+            None => continue,
+        };
+        match groups.iter_mut().find(|(season, day, _)| (*season, *day) == key) {
+            Some(group) => group.2.push(game),
+            None => groups.push((key.0, key.1, vec![game])),
+        }
+    }
+    groups
+}
+
+fn hash_game(game: &GameUpdate) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    // `extra` is a `HashMap`, whose iteration order is randomized per
+    // instance, so hashing `serde_json::to_string(game)` directly would hash
+    // a different key order on essentially every tick even when nothing
+    // changed. Go through `to_value` first: serde_json's `Map` is BTreeMap-
+    // backed (we don't enable the `preserve_order` feature), so it collects
+    // `extra` into a deterministic key order before we serialize and hash it.
+    if let Ok(value) = serde_json::to_value(game) {
+        if let Ok(serialized) = serde_json::to_string(&value) {
+            serialized.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+fn backoff_delay(base_delay: Duration, attempt: u32) -> Duration {
+    let exponential = base_delay.mul_f64(2f64.powi(attempt as i32 - 1));
+    let jitter = base_delay.mul_f64(rand::thread_rng().gen::<f64>());
+    exponential + jitter
+}